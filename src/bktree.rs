@@ -0,0 +1,197 @@
+//! Index BK-tree pour les empreintes binaires (hash d'image, SimHash, etc.), noté par
+//! distance de Hamming. Inspiré du moteur de détection de quasi-doublons de czkawka :
+//! la structure exploite l'inégalité triangulaire pour éviter un scan linéaire de tout
+//! le jeu de données à chaque recherche.
+
+use std::collections::HashMap;
+
+/// Empreinte binaire compacte (ex: un hash perceptuel d'image ou un SimHash de texte).
+pub type ImHash = Vec<u8>;
+
+/// Niveau de tolérance prédéfini, pour éviter à l'appelant de choisir lui-même une
+/// distance de Hamming brute. Les seuils sont exprimés en fraction du nombre de bits
+/// de l'empreinte, sur le modèle des tables de seuils par taille de hash de czkawka.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SimilarityPreset {
+    /// Empreintes strictement identiques.
+    Exact,
+    /// Quasi-doublons : très peu de bits différents.
+    VeryHigh,
+    /// Doublons probables, tolère de petites variations (recompression, recadrage léger).
+    High,
+    /// Ressemblance large, pour du regroupement approximatif.
+    Medium,
+}
+
+impl SimilarityPreset {
+    /// Calcule la distance de Hamming maximale tolérée pour une empreinte de `hash_bits` bits.
+    pub fn max_distance(self, hash_bits: u32) -> u32 {
+        let fraction = match self {
+            SimilarityPreset::Exact => 0.0,
+            SimilarityPreset::VeryHigh => 0.02,
+            SimilarityPreset::High => 0.06,
+            SimilarityPreset::Medium => 0.12,
+        };
+        (hash_bits as f32 * fraction).round() as u32
+    }
+}
+
+/// Distance de Hamming entre deux empreintes : le nombre de bits qui diffèrent.
+///
+/// Calculée par mots de 64 bits avec `count_ones`, qui se traduit par une instruction
+/// popcount matérielle sur la plupart des architectures — donc un popcount "mot par
+/// mot" plutôt qu'un comptage bit à bit. Les derniers octets communs qui ne forment
+/// pas un mot complet sont comptés octet par octet.
+///
+/// `ImHash` ne porte aucune contrainte de longueur, donc `a` et `b` peuvent différer en
+/// taille ; les octets au-delà de la longueur commune sont comptés comme entièrement
+/// différents (8 bits chacun), plutôt que silencieusement ignorés. Tronquer à la plus
+/// courte casserait la propriété de métrique (deux empreintes qui ne diffèrent que sur
+/// la queue tronquée seraient vues comme identiques), ce dont dépend l'élagage par
+/// inégalité triangulaire de [`BKTree::find_within_distance`].
+pub struct Hamming;
+
+impl Hamming {
+    pub fn distance(a: &ImHash, b: &ImHash) -> u32 {
+        let common_len = a.len().min(b.len());
+        let a_words = a[..common_len].chunks_exact(8);
+        let b_words = b[..common_len].chunks_exact(8);
+        let rest = a_words.remainder().iter().zip(b_words.remainder()).map(|(x, y)| (x ^ y).count_ones()).sum::<u32>();
+        let words = a_words
+            .zip(b_words)
+            .map(|(x, y)| {
+                let x = u64::from_ne_bytes(x.try_into().unwrap());
+                let y = u64::from_ne_bytes(y.try_into().unwrap());
+                (x ^ y).count_ones()
+            })
+            .sum::<u32>();
+        let tail_len = a.len().max(b.len()) - common_len;
+        words + rest + tail_len as u32 * 8
+    }
+}
+
+#[derive(Clone)]
+struct Node {
+    id: String,
+    hash: ImHash,
+    // Les enfants sont indexés par leur distance de Hamming au nœud parent.
+    children: HashMap<u32, Node>,
+}
+
+/// BK-tree keyé sur la distance de Hamming entre empreintes binaires.
+///
+/// Chaque nœud place ses enfants dans des branches indexées par leur distance au
+/// nœud, ce qui permet à `find_within_distance` d'élaguer des branches entières grâce
+/// à l'inégalité triangulaire, plutôt que de comparer la requête à chaque empreinte.
+#[derive(Default, Clone)]
+pub struct BKTree {
+    root: Option<Node>,
+}
+
+impl BKTree {
+    pub fn new() -> Self {
+        BKTree { root: None }
+    }
+
+    /// Insère une empreinte dans l'arbre, associée à un identifiant de document.
+    pub fn insert(&mut self, id: String, hash: ImHash) {
+        let Some(root) = &mut self.root else {
+            self.root = Some(Node { id, hash, children: HashMap::new() });
+            return;
+        };
+
+        let mut current = root;
+        loop {
+            let distance = Hamming::distance(&current.hash, &hash);
+            if distance == 0 {
+                // Même empreinte qu'un nœud existant : on remplace le document associé.
+                current.id = id;
+                return;
+            }
+
+            match current.children.entry(distance) {
+                std::collections::hash_map::Entry::Vacant(slot) => {
+                    slot.insert(Node { id, hash, children: HashMap::new() });
+                    return;
+                }
+                std::collections::hash_map::Entry::Occupied(slot) => {
+                    current = slot.into_mut();
+                }
+            }
+        }
+    }
+
+    /// Retourne tous les documents dont l'empreinte est à une distance de Hamming
+    /// inférieure ou égale à `max_distance` de `query`, triés par distance croissante.
+    ///
+    /// À chaque nœud, on calcule `d = distance(nœud, query)` : le nœud est retenu si
+    /// `d <= max_distance`, et on ne descend que dans les branches dont la distance
+    /// d'arête `e` vérifie `d - max_distance <= e <= d + max_distance` (l'inégalité
+    /// triangulaire garantit qu'aucun autre enfant ne peut être assez proche).
+    pub fn find_within_distance(&self, query: &ImHash, max_distance: u32) -> Vec<(String, u32)> {
+        let mut results = Vec::new();
+        if let Some(root) = &self.root {
+            Self::search(root, query, max_distance, &mut results);
+        }
+        results.sort_by_key(|(_, distance)| *distance);
+        results
+    }
+
+    fn search(node: &Node, query: &ImHash, max_distance: u32, results: &mut Vec<(String, u32)>) {
+        let distance = Hamming::distance(&node.hash, query);
+        if distance <= max_distance {
+            results.push((node.id.clone(), distance));
+        }
+
+        let low = distance.saturating_sub(max_distance);
+        let high = distance + max_distance;
+        for (&edge, child) in &node.children {
+            if edge >= low && edge <= high {
+                Self::search(child, query, max_distance, results);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_within_distance_returns_exactly_the_points_inside_the_radius() {
+        let mut tree = BKTree::new();
+        tree.insert("a".to_string(), vec![0b0000_0000]);
+        tree.insert("b".to_string(), vec![0b0000_0001]); // distance 1 de "a"
+        tree.insert("c".to_string(), vec![0b0000_0011]); // distance 2 de "a"
+        tree.insert("d".to_string(), vec![0b1111_1111]); // distance 8 de "a"
+
+        let matches = tree.find_within_distance(&vec![0b0000_0000], 2);
+        assert_eq!(
+            matches,
+            vec![("a".to_string(), 0), ("b".to_string(), 1), ("c".to_string(), 2)]
+        );
+
+        let exact_only = tree.find_within_distance(&vec![0b0000_0000], 0);
+        assert_eq!(exact_only, vec![("a".to_string(), 0)]);
+
+        let too_far = tree.find_within_distance(&vec![0b0000_0000], 3);
+        assert!(!too_far.iter().any(|(id, _)| id == "d"));
+    }
+
+    #[test]
+    fn distance_treats_mismatched_lengths_as_fully_different_on_the_extra_tail() {
+        // Octets communs identiques : seule la queue du plus long compte, à raison de 8
+        // bits par octet en trop (et non du nombre de bits à 1 qu'elle contient).
+        assert_eq!(Hamming::distance(&vec![0b0000_0000], &vec![0b0000_0000, 0b0000_0000]), 8);
+        assert_eq!(Hamming::distance(&vec![0b0000_0000], &vec![0b0000_0000, 0b1111_1111]), 8);
+        assert_eq!(Hamming::distance(&vec![0b0000_0000, 0b0000_0001], &vec![0b0000_0000]), 8);
+    }
+
+    #[test]
+    fn similarity_preset_tolerance_grows_with_fuzziness() {
+        let hash_bits = 64;
+        assert_eq!(SimilarityPreset::Exact.max_distance(hash_bits), 0);
+        assert!(SimilarityPreset::VeryHigh.max_distance(hash_bits) < SimilarityPreset::High.max_distance(hash_bits));
+        assert!(SimilarityPreset::High.max_distance(hash_bits) < SimilarityPreset::Medium.max_distance(hash_bits));
+    }
+}
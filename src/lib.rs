@@ -0,0 +1,602 @@
+//! Bibliothèque rust-tout-rouille : une base de données vectorielle minimale,
+//! avec recherche de similarité, analogies, index binaire par distance de Hamming,
+//! et persistance sur disque.
+
+pub mod bktree;
+pub mod persistence;
+
+use rayon::prelude::*; // Rayon est utilisé pour paralléliser les calculs de similarité
+use serde::{Deserialize, Serialize}; // Dérive la (dé)sérialisation de Document/Collection
+use std::collections::{BinaryHeap, HashMap}; // HashMap pour gérer les collections et documents, BinaryHeap pour le top-k borné
+use std::path::Path;
+use std::sync::Mutex; // Mutex pour protéger les accès concurrents
+use std::cmp::Ordering; // Ordering pour trier les similarités
+use std::cmp::Reverse;
+use ordered_float::NotNan; // Permet un ordre total sur les f32, sans paniquer sur NaN
+use bktree::{BKTree, ImHash, SimilarityPreset};
+use persistence::PersistFormat;
+
+/// Structure représentant un document, identifié par un `id` unique et associé à un vecteur d'embedding.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Document {
+    id: String,         // Identifiant unique du document
+    embedding: Vec<f32>, // Embedding du document (vecteur numérique)
+    metadata: HashMap<String, String>, // Attributs arbitraires (ex: "lang" => "fr") pour le filtrage
+}
+
+/// Métrique de distance utilisée pour noter la proximité entre deux embeddings.
+///
+/// `Cosine` et `DotProduct` sont "plus grand = plus similaire" ; les embeddings sont
+/// normalisés en L2 à l'insertion quand la métrique est `Cosine`, ce qui permet de
+/// remplacer le calcul du cosinus par un simple produit scalaire dans la boucle chaude.
+/// `Euclidean` est "plus petit = plus similaire" (distance), tandis que `NegativeL2`
+/// en est l'opposé (`-distance²`) pour rester "plus grand = plus similaire" comme les
+/// deux premières métriques, au prix de scores qui ne sont pas des distances réelles.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DistanceMetric {
+    #[default]
+    Cosine,
+    DotProduct,
+    Euclidean,
+    NegativeL2,
+}
+
+impl DistanceMetric {
+    /// Indique si un score plus élevé signifie "plus similaire" pour cette métrique.
+    fn higher_is_better(self) -> bool {
+        !matches!(self, DistanceMetric::Euclidean)
+    }
+
+    /// Calcule le score brut entre deux vecteurs pour cette métrique.
+    ///
+    /// Pour `Cosine`, les deux vecteurs doivent déjà être de norme L2 unitaire (c'est le
+    /// cas des embeddings stockés, normalisés à l'insertion ; les appelants doivent
+    /// normaliser la requête une seule fois en amont de la boucle chaude, voir
+    /// [`Collection::get_similar_documents_filtered`]) : le score se réduit alors à un
+    /// simple produit scalaire, identique à `DotProduct`.
+    fn score(self, a: &[f32], b: &[f32]) -> f32 {
+        match self {
+            DistanceMetric::Cosine | DistanceMetric::DotProduct => a.iter().zip(b).map(|(x, y)| x * y).sum(),
+            DistanceMetric::Euclidean => euclidean_distance(a, b),
+            DistanceMetric::NegativeL2 => -euclidean_distance(a, b).powi(2),
+        }
+    }
+}
+
+/// Normalise un vecteur en place pour qu'il soit de norme L2 unitaire. Ne fait rien si
+/// le vecteur est nul, pour éviter une division par zéro.
+fn l2_normalize(embedding: &mut [f32]) {
+    let norm = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for x in embedding.iter_mut() {
+            *x /= norm;
+        }
+    }
+}
+
+/// Structure représentant une collection de documents.
+/// Chaque collection est une HashMap avec des `id` comme clés et des `Document` comme valeurs.
+#[derive(Default, Clone, Serialize, Deserialize)]
+pub struct Collection {
+    name: String,                      // Nom de la collection
+    documents: HashMap<String, Document>, // Documents stockés dans la collection
+    metric: DistanceMetric,            // Métrique utilisée pour noter la similarité des documents
+    // Pas persisté : `Database::save`/`load` ne couvrent que les embeddings flottants,
+    // pas les empreintes binaires ajoutées via `add_binary_document`.
+    #[serde(skip)]
+    binary_index: BKTree,
+}
+
+impl Collection {
+    /// Crée une nouvelle collection avec un nom donné, notée par défaut avec la
+    /// similarité cosinus.
+    pub fn new(name: &str) -> Self {
+        Collection::with_metric(name, DistanceMetric::default())
+    }
+
+    /// Crée une nouvelle collection avec un nom et une métrique de distance donnés.
+    pub fn with_metric(name: &str, metric: DistanceMetric) -> Self {
+        Collection {
+            name: name.to_string(),
+            documents: HashMap::new(),
+            metric,
+            binary_index: BKTree::new(),
+        }
+    }
+
+    /// Ajoute une empreinte binaire (hash perceptuel, SimHash, ...) à l'index BK-tree de
+    /// la collection, pour une recherche par distance de Hamming avec
+    /// [`Collection::find_within_distance`].
+    pub fn add_binary_document(&mut self, id: &str, hash: ImHash) {
+        self.binary_index.insert(id.to_string(), hash);
+    }
+
+    /// Retourne les identifiants des documents dont l'empreinte binaire est à une
+    /// distance de Hamming inférieure ou égale à `max_distance` de `query_hash`, triés
+    /// par distance croissante. Grâce à l'élagage par inégalité triangulaire du
+    /// BK-tree, ce coût est sous-linéaire plutôt qu'un scan de tout l'index.
+    pub fn find_within_distance(&self, query_hash: &ImHash, max_distance: u32) -> Vec<(String, u32)> {
+        self.binary_index.find_within_distance(query_hash, max_distance)
+    }
+
+    /// Comme [`Collection::find_within_distance`], mais à partir d'un niveau de
+    /// tolérance prédéfini plutôt qu'une distance de Hamming brute.
+    pub fn find_similar_hashes(&self, query_hash: &ImHash, preset: SimilarityPreset) -> Vec<(String, u32)> {
+        let hash_bits = query_hash.len() as u32 * 8;
+        self.find_within_distance(query_hash, preset.max_distance(hash_bits))
+    }
+
+    /// Ajoute un document à la collection, avec des attributs de métadonnées optionnels
+    /// (ex: `{"lang": "fr"}`) qui pourront ensuite servir à filtrer les recherches.
+    ///
+    /// Quand la collection utilise la métrique `Cosine`, l'embedding est normalisé en
+    /// L2 avant stockage, ce qui permet au calcul de similarité de se réduire à un
+    /// simple produit scalaire.
+    pub fn add_document(&mut self, id: &str, mut embedding: Vec<f32>, metadata: Option<HashMap<String, String>>) {
+        if self.metric == DistanceMetric::Cosine {
+            l2_normalize(&mut embedding);
+        }
+
+        self.documents.insert(
+            id.to_string(),
+            Document {
+                id: id.to_string(),
+                embedding,
+                metadata: metadata.unwrap_or_default(),
+            },
+        );
+    }
+
+    /// Supprime un document de la collection en utilisant son `id`.
+    pub fn remove_document(&mut self, id: &str) {
+        self.documents.remove(id);
+    }
+
+    /// Retourne les `top_n` documents les plus similaires à un embedding donné.
+    pub fn get_similar_documents(&self, query_embedding: &[f32], top_n: usize) -> Vec<(String, f32)> {
+        self.get_similar_documents_filtered(query_embedding, top_n, |_| true)
+    }
+
+    /// Comme [`Collection::get_similar_documents`], mais ne considère que les documents
+    /// pour lesquels `predicate` renvoie `true` (ex: restreindre la recherche aux documents
+    /// dont les métadonnées valent `lang=fr`). Les documents exclus sont filtrés avant le
+    /// calcul du score, donc ils ne coûtent rien au scan parallèle.
+    ///
+    /// Plutôt que de trier l'ensemble des scores (O(N log N)), on maintient un tas
+    /// min borné à `top_n` éléments : un score n'est conservé que s'il bat le plus
+    /// mauvais candidat déjà retenu. Chaque thread rayon a son propre tas, fusionnés
+    /// ensuite par un `reduce`, ce qui évite tout verrou pendant le calcul parallèle.
+    pub fn get_similar_documents_filtered(
+        &self,
+        query_embedding: &[f32],
+        top_n: usize,
+        predicate: impl Fn(&Document) -> bool + Sync,
+    ) -> Vec<(String, f32)> {
+        // Les embeddings stockés sont déjà normalisés en L2 quand la métrique est `Cosine`
+        // (voir `add_document`) ; on normalise la requête une seule fois ici plutôt que
+        // dans la boucle chaude, pour que `DistanceMetric::score` se réduise à un simple
+        // produit scalaire pour chaque document comparé.
+        let normalized_query_storage;
+        let query_embedding: &[f32] = if self.metric == DistanceMetric::Cosine {
+            let mut query = query_embedding.to_vec();
+            l2_normalize(&mut query);
+            normalized_query_storage = query;
+            &normalized_query_storage
+        } else {
+            query_embedding
+        };
+
+        let heap = self
+            .documents
+            .par_iter()
+            .filter(|(_, doc)| predicate(doc))
+            .fold(
+                || BinaryHeap::with_capacity(top_n + 1),
+                |mut heap: BinaryHeap<Reverse<WordSimilarity>>, (_, doc)| {
+                    let score = self.metric.score(&doc.embedding, query_embedding);
+                    push_bounded(&mut heap, WordSimilarity::new(doc.id.clone(), score, self.metric), top_n);
+                    heap
+                },
+            )
+            .reduce(
+                || BinaryHeap::with_capacity(top_n + 1),
+                |mut acc, other| {
+                    for Reverse(candidate) in other.into_iter() {
+                        push_bounded(&mut acc, candidate, top_n);
+                    }
+                    acc
+                },
+            );
+
+        // On draine le tas puis on trie par clé décroissante (et par `id` en cas d'égalité),
+        // ce qui donne un ordre décroissant pour les métriques "plus grand = plus similaire"
+        // et croissant pour `Euclidean`, puisque sa clé est l'opposé de la distance.
+        let mut results: Vec<WordSimilarity> = heap.into_iter().map(|Reverse(ws)| ws).collect();
+        results.sort_by(|a, b| b.cmp(a));
+        results.into_iter().map(|ws| (ws.id, ws.value.into_inner())).collect()
+    }
+
+    /// Résout une analogie « `a` est à `b` ce que `c` est à ? » en cherchant les documents
+    /// les plus proches du vecteur `emb(b) - emb(a) + emb(c)`.
+    ///
+    /// Retourne `None` si l'un des trois identifiants est absent de la collection.
+    /// Les identifiants `a`, `b` et `c` sont exclus des résultats, car ce sont les
+    /// termes de la question et non des réponses possibles.
+    pub fn analogy(&self, a: &str, b: &str, c: &str, top_n: usize) -> Option<Vec<(String, f32)>> {
+        let emb_a = &self.documents.get(a)?.embedding;
+        let emb_b = &self.documents.get(b)?.embedding;
+        let emb_c = &self.documents.get(c)?.embedding;
+
+        let target: Vec<f32> = emb_b
+            .iter()
+            .zip(emb_a.iter())
+            .zip(emb_c.iter())
+            .map(|((b, a), c)| b - a + c)
+            .collect();
+
+        let excluded = [a, b, c];
+        let results = self
+            .get_similar_documents(&target, top_n + excluded.len())
+            .into_iter()
+            .filter(|(id, _)| !excluded.contains(&id.as_str()))
+            .take(top_n)
+            .collect();
+
+        Some(results)
+    }
+
+    /// Trouve les documents les plus similaires à un document déjà stocké, désigné par
+    /// son `id`, sans que l'appelant ait besoin de refournir son embedding.
+    ///
+    /// Le document `id` lui-même est exclu des candidats. Comme `offset + limit` borne
+    /// le nombre de résultats utiles dès le départ, on réutilise le même tas min borné
+    /// que [`Collection::get_similar_documents_filtered`] (taille `offset + limit`)
+    /// plutôt que de trier toute la collection : le tas contient exactement le même
+    /// préfixe que le tri complet, donc filtrer par `ranking_score_threshold` après coup
+    /// donne un résultat identique, ce seuil ne faisant que tronquer la queue d'un
+    /// classement déjà trié par score. Retourne `None` si `id` n'existe pas dans la
+    /// collection.
+    pub fn get_similar_to_id(
+        &self,
+        id: &str,
+        offset: usize,
+        limit: usize,
+        ranking_score_threshold: Option<f32>,
+    ) -> Option<Vec<(String, f32)>> {
+        let query_embedding = &self.documents.get(id)?.embedding;
+        let capacity = offset + limit;
+
+        let heap = self
+            .documents
+            .par_iter()
+            .filter(|(doc_id, _)| doc_id.as_str() != id)
+            .fold(
+                || BinaryHeap::with_capacity(capacity + 1),
+                |mut heap: BinaryHeap<Reverse<WordSimilarity>>, (_, doc)| {
+                    let score = self.metric.score(&doc.embedding, query_embedding);
+                    push_bounded(&mut heap, WordSimilarity::new(doc.id.clone(), score, self.metric), capacity);
+                    heap
+                },
+            )
+            .reduce(
+                || BinaryHeap::with_capacity(capacity + 1),
+                |mut acc, other| {
+                    for Reverse(candidate) in other.into_iter() {
+                        push_bounded(&mut acc, candidate, capacity);
+                    }
+                    acc
+                },
+            );
+
+        let higher_is_better = self.metric.higher_is_better();
+        let mut results: Vec<WordSimilarity> = heap.into_iter().map(|Reverse(ws)| ws).collect();
+        results.sort_by(|a, b| b.cmp(a));
+
+        let results = results
+            .into_iter()
+            .filter(|ws| {
+                ranking_score_threshold.is_none_or(|threshold| {
+                    let sim = ws.value.into_inner();
+                    if higher_is_better { sim >= threshold } else { sim <= threshold }
+                })
+            })
+            .skip(offset)
+            .take(limit)
+            .map(|ws| (ws.id, ws.value.into_inner()))
+            .collect();
+
+        Some(results)
+    }
+}
+
+/// Pousse `candidate` dans un tas min borné à `capacity` éléments, en éliminant le
+/// plus mauvais candidat si le tas déborde.
+fn push_bounded(heap: &mut BinaryHeap<Reverse<WordSimilarity>>, candidate: WordSimilarity, capacity: usize) {
+    heap.push(Reverse(candidate));
+    if heap.len() > capacity {
+        heap.pop();
+    }
+}
+
+/// Score de similarité associé à un document, ordonné totalement (NaN exclu via `NotNan`)
+/// avec un départage sur `id` pour un tri déterministe en cas d'égalité.
+///
+/// `key` sert uniquement à l'ordre du tas : pour les métriques "plus petit = plus
+/// similaire" (ex: `Euclidean`), elle vaut l'opposé de `value` afin que "plus grand `key`
+/// = plus similaire" reste vrai pour toutes les métriques, ce qui permet de réutiliser le
+/// même tas et le même tri pour chacune d'elles. `value` est le score brut retourné à
+/// l'appelant (ex: une vraie distance pour `Euclidean`, pas sa version négée).
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct WordSimilarity {
+    key: NotNan<f32>,
+    value: NotNan<f32>,
+    id: String,
+}
+
+impl WordSimilarity {
+    fn new(id: String, value: f32, metric: DistanceMetric) -> Self {
+        let key = if metric.higher_is_better() { value } else { -value };
+        WordSimilarity {
+            key: NotNan::new(key).unwrap_or_else(|_| NotNan::new(f32::NEG_INFINITY).unwrap()),
+            value: NotNan::new(value).unwrap_or_else(|_| NotNan::new(f32::NEG_INFINITY).unwrap()),
+            id,
+        }
+    }
+}
+
+impl Ord for WordSimilarity {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key).then_with(|| self.id.cmp(&other.id))
+    }
+}
+
+impl PartialOrd for WordSimilarity {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Structure représentant une base de données de collections.
+/// Chaque collection est identifiée par un nom unique.
+pub struct Database {
+    collections: Mutex<HashMap<String, Collection>>, // Protéger les collections contre les accès concurrents
+}
+
+impl Default for Database {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Database {
+    /// Crée une nouvelle base de données.
+    pub fn new() -> Self {
+        Database {
+            collections: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Crée une nouvelle collection avec un nom donné et l'ajoute à la base.
+    pub fn create_collection(&self, name: &str) {
+        let mut collections = self.collections.lock().unwrap();
+        collections.insert(name.to_string(), Collection::new(name));
+    }
+
+    /// Crée une nouvelle collection avec un nom et une métrique de distance donnés.
+    pub fn create_collection_with_metric(&self, name: &str, metric: DistanceMetric) {
+        let mut collections = self.collections.lock().unwrap();
+        collections.insert(name.to_string(), Collection::with_metric(name, metric));
+    }
+
+    /// Supprime une collection de la base par son nom.
+    pub fn delete_collection(&self, name: &str) {
+        let mut collections = self.collections.lock().unwrap();
+        collections.remove(name);
+    }
+
+    /// Récupère une collection par son nom, si elle existe.
+    pub fn get_collection(&self, name: &str) -> Option<Collection> {
+        let collections = self.collections.lock().unwrap();
+        collections.get(name).cloned()
+    }
+
+    /// Écrit toutes les collections sur disque dans le `format` demandé (JSON lisible
+    /// ou binaire compact), en verrouillant le temps de prendre un instantané cohérent.
+    pub fn save(&self, path: impl AsRef<Path>, format: PersistFormat) -> std::io::Result<()> {
+        let collections = self.collections.lock().unwrap();
+        persistence::save(&collections, path.as_ref(), format)
+    }
+
+    /// Recharge une base de données précédemment écrite par [`Database::save`] dans le
+    /// même `format`.
+    pub fn load(path: impl AsRef<Path>, format: PersistFormat) -> std::io::Result<Self> {
+        let collections = persistence::load(path.as_ref(), format)?;
+        Ok(Database {
+            collections: Mutex::new(collections),
+        })
+    }
+}
+
+/// Calcule la distance euclidienne (L2) entre deux vecteurs.
+fn euclidean_distance(vec1: &[f32], vec2: &[f32]) -> f32 {
+    vec1.iter()
+        .zip(vec2)
+        .map(|(a, b)| (a - b).powi(2))
+        .sum::<f32>()
+        .sqrt()
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn analogy_excludes_inputs_and_finds_expected_relation() {
+        // On reste en `DotProduct` pour que les embeddings stockés soient exactement
+        // ceux fournis (pas de normalisation L2), afin de vérifier l'arithmétique
+        // `emb(b) - emb(a) + emb(c)` telle quelle.
+        let mut collection = Collection::with_metric("analogy", DistanceMetric::DotProduct);
+        collection.add_document("man", vec![1.0, 0.0, 0.0], None);
+        collection.add_document("king", vec![1.0, 1.0, 0.0], None);
+        collection.add_document("woman", vec![0.0, 0.0, 1.0], None);
+        collection.add_document("queen", vec![0.0, 1.0, 1.0], None); // king - man + woman
+        collection.add_document("unrelated", vec![-5.0, -5.0, -5.0], None);
+
+        let results = collection.analogy("man", "king", "woman", 2).expect("les trois ids existent");
+
+        assert_eq!(results[0].0, "queen");
+        assert!(!results.iter().any(|(id, _)| id == "man" || id == "king" || id == "woman"));
+    }
+
+    #[test]
+    fn analogy_returns_none_when_an_id_is_missing() {
+        let mut collection = Collection::new("analogy");
+        collection.add_document("man", vec![1.0, 0.0, 0.0], None);
+
+        assert!(collection.analogy("man", "missing", "woman", 2).is_none());
+    }
+
+    #[test]
+    fn get_similar_to_id_excludes_self_and_paginates() {
+        let mut collection = Collection::with_metric("sim", DistanceMetric::DotProduct);
+        collection.add_document("a", vec![1.0, 0.0], None);
+        collection.add_document("b", vec![0.9, 0.1], None);
+        collection.add_document("c", vec![0.5, 0.5], None);
+        collection.add_document("d", vec![0.0, 1.0], None);
+
+        let all = collection.get_similar_to_id("a", 0, 10, None).expect("a existe");
+        assert_eq!(
+            all.iter().map(|(id, _)| id.as_str()).collect::<Vec<_>>(),
+            vec!["b", "c", "d"]
+        );
+
+        let page = collection.get_similar_to_id("a", 1, 1, None).expect("a existe");
+        assert_eq!(page, vec![("c".to_string(), 0.5)]);
+    }
+
+    #[test]
+    fn get_similar_to_id_applies_score_threshold() {
+        let mut collection = Collection::with_metric("sim", DistanceMetric::DotProduct);
+        collection.add_document("a", vec![1.0, 0.0], None);
+        collection.add_document("b", vec![0.9, 0.1], None);
+        collection.add_document("c", vec![0.5, 0.5], None);
+        collection.add_document("d", vec![0.0, 1.0], None);
+
+        let results = collection
+            .get_similar_to_id("a", 0, 10, Some(0.5))
+            .expect("a existe");
+
+        assert_eq!(
+            results.iter().map(|(id, _)| id.as_str()).collect::<Vec<_>>(),
+            vec!["b", "c"]
+        );
+    }
+
+    #[test]
+    fn get_similar_to_id_returns_none_for_missing_id() {
+        let collection = Collection::new("sim");
+        assert!(collection.get_similar_to_id("missing", 0, 10, None).is_none());
+    }
+
+    #[test]
+    fn get_similar_documents_filtered_drops_non_matching_docs() {
+        let mut collection = Collection::with_metric("filtered", DistanceMetric::DotProduct);
+
+        let mut fr_metadata = HashMap::new();
+        fr_metadata.insert("lang".to_string(), "fr".to_string());
+        collection.add_document("fr1", vec![1.0, 0.0], Some(fr_metadata.clone()));
+        collection.add_document("fr2", vec![0.9, 0.1], Some(fr_metadata));
+
+        let mut en_metadata = HashMap::new();
+        en_metadata.insert("lang".to_string(), "en".to_string());
+        collection.add_document("en1", vec![1.0, 0.0], Some(en_metadata));
+
+        let results = collection.get_similar_documents_filtered(
+            &[1.0, 0.0],
+            10,
+            |doc| doc.metadata.get("lang").map(String::as_str) == Some("fr"),
+        );
+
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|(id, _)| id == "fr1" || id == "fr2"));
+    }
+
+    #[test]
+    fn euclidean_sorts_ascending_by_distance() {
+        let mut collection = Collection::with_metric("euclidean", DistanceMetric::Euclidean);
+        collection.add_document("near", vec![1.0, 0.0], None);
+        collection.add_document("far", vec![10.0, 0.0], None);
+
+        let results = collection.get_similar_documents(&[0.0, 0.0], 2);
+
+        assert_eq!(results[0].0, "near");
+        assert_eq!(results[1].0, "far");
+        assert!(results[0].1 < results[1].1);
+    }
+
+    #[test]
+    fn negative_l2_sorts_descending_like_cosine_and_dot_product() {
+        let mut collection = Collection::with_metric("negative_l2", DistanceMetric::NegativeL2);
+        collection.add_document("near", vec![1.0, 0.0], None);
+        collection.add_document("far", vec![10.0, 0.0], None);
+
+        let results = collection.get_similar_documents(&[0.0, 0.0], 2);
+
+        assert_eq!(results[0].0, "near");
+        assert_eq!(results[1].0, "far");
+        assert!(results[0].1 > results[1].1); // "near" a le score le moins négatif
+    }
+
+    #[test]
+    fn cosine_normalizes_embeddings_at_insert_time() {
+        let mut collection = Collection::with_metric("cosine", DistanceMetric::Cosine);
+        collection.add_document("doc", vec![3.0, 4.0], None); // norme 5
+
+        let stored = &collection.documents["doc"].embedding;
+        let norm = (stored[0] * stored[0] + stored[1] * stored[1]).sqrt();
+        assert!((norm - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn create_collection_with_metric_sets_the_chosen_metric() {
+        let db = Database::new();
+        db.create_collection_with_metric("euclidean", DistanceMetric::Euclidean);
+
+        let collection = db.get_collection("euclidean").expect("la collection vient d'être créée");
+        assert_eq!(collection.metric, DistanceMetric::Euclidean);
+    }
+
+    #[test]
+    fn collection_binary_documents_are_searchable_by_hamming_distance() {
+        let mut collection = Collection::new("hashes");
+        collection.add_binary_document("a", vec![0b0000_0000]);
+        collection.add_binary_document("b", vec![0b0000_0001]); // distance 1 de "a"
+        collection.add_binary_document("c", vec![0b1111_1111]); // distance 8 de "a"
+
+        let matches = collection.find_within_distance(&vec![0b0000_0000], 1);
+        assert_eq!(matches, vec![("a".to_string(), 0), ("b".to_string(), 1)]);
+
+        let exact_only = collection.find_similar_hashes(&vec![0b0000_0000], SimilarityPreset::Exact);
+        assert_eq!(exact_only, vec![("a".to_string(), 0)]);
+    }
+
+    #[test]
+    fn database_save_and_load_round_trip_a_collection() {
+        let db = Database::new();
+        db.create_collection_with_metric("docs", DistanceMetric::DotProduct);
+        {
+            // `get_collection` renvoie un clone ; on passe par le verrou pour modifier
+            // la collection réellement stockée dans la base.
+            let mut collections = db.collections.lock().unwrap();
+            collections.get_mut("docs").unwrap().add_document("doc1", vec![0.1, 0.2, 0.3], None);
+        }
+
+        let path = std::env::temp_dir().join("rust_tout_rouille_test_database_round_trip.json");
+        db.save(&path, PersistFormat::Json).unwrap();
+        let reloaded = Database::load(&path, PersistFormat::Json).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let reloaded_collection = reloaded.get_collection("docs").expect("la collection a été persistée");
+        assert_eq!(reloaded_collection.metric, DistanceMetric::DotProduct);
+        assert_eq!(reloaded_collection.documents["doc1"].embedding, vec![0.1, 0.2, 0.3]);
+    }
+}
@@ -0,0 +1,217 @@
+//! Persistance d'une [`Database`](crate::Database) sur disque.
+//!
+//! Deux formats sont proposés : un mode JSON lisible, pratique pour l'inspection et
+//! le débogage, et un mode binaire compact où les embeddings sont écrits comme des
+//! blocs de `f32` little-endian bruts, sur le modèle des lecteurs/écrivains de
+//! formats d'embeddings de mots (type word2vec), pour charger vite les grosses
+//! collections.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::{Collection, DistanceMetric, Document};
+
+/// Format de (dé)sérialisation choisi pour `Database::save` / `Database::load`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PersistFormat {
+    Json,
+    Binary,
+}
+
+pub fn save(collections: &HashMap<String, Collection>, path: &Path, format: PersistFormat) -> io::Result<()> {
+    match format {
+        PersistFormat::Json => save_json(collections, path),
+        PersistFormat::Binary => save_binary(collections, path),
+    }
+}
+
+pub fn load(path: &Path, format: PersistFormat) -> io::Result<HashMap<String, Collection>> {
+    match format {
+        PersistFormat::Json => load_json(path),
+        PersistFormat::Binary => load_binary(path),
+    }
+}
+
+fn save_json(collections: &HashMap<String, Collection>, path: &Path) -> io::Result<()> {
+    let writer = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(writer, collections).map_err(to_io_error)
+}
+
+fn load_json(path: &Path) -> io::Result<HashMap<String, Collection>> {
+    let reader = BufReader::new(File::open(path)?);
+    serde_json::from_reader(reader).map_err(to_io_error)
+}
+
+fn to_io_error(err: serde_json::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+fn save_binary(collections: &HashMap<String, Collection>, path: &Path) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+
+    write_u64(&mut writer, collections.len() as u64)?;
+    for collection in collections.values() {
+        write_string(&mut writer, &collection.name)?;
+        write_metric(&mut writer, collection.metric)?;
+
+        write_u64(&mut writer, collection.documents.len() as u64)?;
+        for doc in collection.documents.values() {
+            write_string(&mut writer, &doc.id)?;
+
+            write_u64(&mut writer, doc.embedding.len() as u64)?;
+            for value in &doc.embedding {
+                writer.write_all(&value.to_le_bytes())?;
+            }
+
+            write_u64(&mut writer, doc.metadata.len() as u64)?;
+            for (key, value) in &doc.metadata {
+                write_string(&mut writer, key)?;
+                write_string(&mut writer, value)?;
+            }
+        }
+    }
+
+    writer.flush()
+}
+
+fn load_binary(path: &Path) -> io::Result<HashMap<String, Collection>> {
+    let mut reader = BufReader::new(File::open(path)?);
+
+    let num_collections = read_u64(&mut reader)?;
+    let mut collections = HashMap::with_capacity(num_collections as usize);
+    for _ in 0..num_collections {
+        let name = read_string(&mut reader)?;
+        let metric = read_metric(&mut reader)?;
+        let mut collection = Collection::with_metric(&name, metric);
+
+        let num_documents = read_u64(&mut reader)?;
+        for _ in 0..num_documents {
+            let id = read_string(&mut reader)?;
+
+            let embedding_len = read_u64(&mut reader)? as usize;
+            let mut embedding = Vec::with_capacity(embedding_len);
+            let mut buf = [0u8; 4];
+            for _ in 0..embedding_len {
+                reader.read_exact(&mut buf)?;
+                embedding.push(f32::from_le_bytes(buf));
+            }
+
+            let metadata_len = read_u64(&mut reader)? as usize;
+            let mut metadata = HashMap::with_capacity(metadata_len);
+            for _ in 0..metadata_len {
+                let key = read_string(&mut reader)?;
+                let value = read_string(&mut reader)?;
+                metadata.insert(key, value);
+            }
+
+            collection.documents.insert(id.clone(), Document { id, embedding, metadata });
+        }
+
+        collections.insert(name, collection);
+    }
+
+    Ok(collections)
+}
+
+fn write_u64(writer: &mut impl Write, value: u64) -> io::Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+/// Écrit une chaîne préfixée par sa longueur en octets (length-prefixed), pour pouvoir
+/// la relire sans séparateur.
+fn write_string(writer: &mut impl Write, value: &str) -> io::Result<()> {
+    write_u64(writer, value.len() as u64)?;
+    writer.write_all(value.as_bytes())
+}
+
+fn read_string(reader: &mut impl Read) -> io::Result<String> {
+    let len = read_u64(reader)? as usize;
+    let mut buf = vec![0u8; len];
+    reader.read_exact(&mut buf)?;
+    String::from_utf8(buf).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+fn write_metric(writer: &mut impl Write, metric: DistanceMetric) -> io::Result<()> {
+    let tag: u8 = match metric {
+        DistanceMetric::Cosine => 0,
+        DistanceMetric::DotProduct => 1,
+        DistanceMetric::Euclidean => 2,
+        DistanceMetric::NegativeL2 => 3,
+    };
+    writer.write_all(&[tag])
+}
+
+fn read_metric(reader: &mut impl Read) -> io::Result<DistanceMetric> {
+    let mut tag = [0u8; 1];
+    reader.read_exact(&mut tag)?;
+    match tag[0] {
+        0 => Ok(DistanceMetric::Cosine),
+        1 => Ok(DistanceMetric::DotProduct),
+        2 => Ok(DistanceMetric::Euclidean),
+        3 => Ok(DistanceMetric::NegativeL2),
+        other => Err(io::Error::new(io::ErrorKind::InvalidData, format!("tag de métrique inconnu: {other}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_collections() -> HashMap<String, Collection> {
+        let mut collection = Collection::with_metric("docs", DistanceMetric::DotProduct);
+
+        let mut metadata = HashMap::new();
+        metadata.insert("lang".to_string(), "fr".to_string());
+        collection.add_document("doc1", vec![0.1, 0.2, 0.3], Some(metadata));
+        collection.add_document("doc2", vec![0.4, 0.5, 0.6], None);
+
+        let mut collections = HashMap::new();
+        collections.insert("docs".to_string(), collection);
+        collections
+    }
+
+    fn assert_round_trips(original: &HashMap<String, Collection>, loaded: &HashMap<String, Collection>) {
+        let original_collection = &original["docs"];
+        let loaded_collection = &loaded["docs"];
+        assert_eq!(loaded_collection.metric, original_collection.metric);
+        assert_eq!(loaded_collection.documents.len(), original_collection.documents.len());
+
+        for (id, doc) in &original_collection.documents {
+            let reloaded = &loaded_collection.documents[id];
+            assert_eq!(reloaded.embedding, doc.embedding);
+            assert_eq!(reloaded.metadata, doc.metadata);
+        }
+    }
+
+    #[test]
+    fn json_round_trip_preserves_documents_metadata_and_metric() {
+        let collections = sample_collections();
+        let path = std::env::temp_dir().join("rust_tout_rouille_test_round_trip.json");
+
+        save(&collections, &path, PersistFormat::Json).unwrap();
+        let loaded = load(&path, PersistFormat::Json).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_round_trips(&collections, &loaded);
+    }
+
+    #[test]
+    fn binary_round_trip_preserves_documents_metadata_and_metric() {
+        let collections = sample_collections();
+        let path = std::env::temp_dir().join("rust_tout_rouille_test_round_trip.bin");
+
+        save(&collections, &path, PersistFormat::Binary).unwrap();
+        let loaded = load(&path, PersistFormat::Binary).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_round_trips(&collections, &loaded);
+    }
+}